@@ -1,6 +1,7 @@
 use anchor_lang::{
   prelude::*,
-  system_program::{create_account, CreateAccount},
+  solana_program::program_option::COption,
+  system_program::{create_account, transfer, CreateAccount, Transfer},
 };
 use anchor_spl::{
   associated_token::AssociatedToken,
@@ -9,12 +10,16 @@ use anchor_spl::{
 use spl_tlv_account_resolution::{
   state::ExtraAccountMetaList,
   account::ExtraAccountMeta,
+  seeds::Seed,
 };
-use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
 use solana_program::{
     sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
 };
 
+#[cfg(feature = "offchain")]
+pub mod resolve;
+
 // Token Mint account using this hook program 6NBsYsoj5aRt7X9cmUksv8aeLtubErmLkGZ8DujrtoS3
 // Hook program ID:
 declare_id!("B2tN85yQ3ta8965WYns4DnitH9YJ9JnBsPb1dF1ghb15");
@@ -27,12 +32,72 @@ pub const TOKEN_2022_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
     0xb6, 0x1a, 0xfc, 0x4d, 0x83, 0xb9, 0xd, 0x27, 0xfe, 0xbd, 0xf9, 0x28, 0xd8, 0xa1, 0x8b, 0xfc
 ]);
 
+// The authority allowed to issue HumanCredential attestations. This is an arbitrary placeholder
+// key, unrelated to TOKEN_2022_PROGRAM_ID — swap it for a real attestation provider's key before
+// deployment.
+pub const ATTESTATION_AUTHORITY: Pubkey = Pubkey::new_from_array([
+    0x44, 0x20, 0x82, 0x3c, 0xfd, 0xe6, 0xf1, 0xc2, 0x6b, 0x30, 0xf9, 0xe, 0xc7, 0xdd, 0x1, 0xe4,
+    0x88, 0x75, 0x34, 0xa2, 0xf, 0xb, 0xd, 0x4, 0xc3, 0x6e, 0xd8, 0xe, 0x71, 0xe0, 0xfd, 0x77
+]);
+
+// The admin allowed to manage the Denylist PDA (add_to_denylist/remove_from_denylist). This is
+// an arbitrary placeholder key, unrelated to TOKEN_2022_PROGRAM_ID — swap it for a real
+// compliance-operator key before deployment.
+pub const DENYLIST_ADMIN: Pubkey = Pubkey::new_from_array([
+    0xb0, 0x76, 0x70, 0xeb, 0x94, 0xb, 0xd5, 0x33, 0x5f, 0x97, 0x3d, 0xaa, 0xd8, 0x61, 0x9b, 0x91,
+    0xff, 0xc9, 0x11, 0xf5, 0x7c, 0xce, 0xd4, 0x58, 0xbb, 0xbf, 0x2c, 0xe0, 0x37, 0x53, 0xc9, 0xbd
+]);
+
+// Upper bound on the number of blocked pubkeys a single Denylist PDA can hold.
+pub const MAX_DENYLIST_LEN: usize = 512;
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Caller is not authorized to invoke this instruction")]
     UnauthorizedCaller,
-    #[msg("Invalid instruction data")]
-    InvalidInstruction,
+    #[msg("Source owner has no verified human credential")]
+    HumanNotVerified,
+    #[msg("Source owner's human credential has expired")]
+    CredentialExpired,
+    #[msg("Source owner or destination account is on the denylist")]
+    BlockedAddress,
+    #[msg("Denylist is full")]
+    DenylistFull,
+    #[msg("Address is not on the denylist")]
+    NotInDenylist,
+}
+
+// Total account size (including the 8-byte discriminator) for a Denylist holding `len` entries.
+fn denylist_account_size(len: usize) -> usize {
+    8 + 32 + 1 + 4 + len * 32
+}
+
+// Account ordering seen by the token program's `Execute` CPI:
+// 0 source, 1 mint, 2 destination, 3 owner, 4 validation (this account), then extras below
+// in the order listed here: 5 human_credential, 6 instructions_sysvar, 7 denylist.
+fn extra_account_metas(mint: &Pubkey, program_id: &Pubkey) -> Result<Vec<ExtraAccountMeta>> {
+    let (denylist, _) = Pubkey::find_program_address(&[b"denylist", mint.as_ref()], program_id);
+
+    Ok(vec![
+        // Resolve the owner's HumanCredential PDA: seeds = [b"human", owner]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"human".to_vec() },
+                Seed::AccountKey { index: 3 },
+            ],
+            false,
+            false,
+        )?,
+        // Include the instructions sysvar account
+        ExtraAccountMeta::new_with_pubkey(
+            &solana_program::sysvar::instructions::ID,
+            false,
+            false,
+        )?,
+        // The Denylist PDA is specific to this mint, so it's resolved once here as a fixed
+        // pubkey rather than re-derived from seeds on every transfer.
+        ExtraAccountMeta::new_with_pubkey(&denylist, false, false)?,
+    ])
 }
 
 #[program]
@@ -43,15 +108,7 @@ pub mod transfer_hook {
       ctx: Context<InitializeExtraAccountMetaList>,
   ) -> Result<()> {
 
-      // The `addExtraAccountsToInstruction` JS helper function resolving incorrectly
-      let account_metas = vec![
-          // Include the instructions sysvar account
-          ExtraAccountMeta::new_with_pubkey(
-              &solana_program::sysvar::instructions::ID,
-              false,
-              false,
-          )?,
-      ];
+      let account_metas = extra_account_metas(&ctx.accounts.mint.key(), ctx.program_id)?;
 
       // calculate account size
       let account_size = ExtraAccountMetaList::size_of(account_metas.len())? as u64;
@@ -86,50 +143,193 @@ pub mod transfer_hook {
           &account_metas,
       )?;
 
+      let denylist = &mut ctx.accounts.denylist;
+      denylist.mint = ctx.accounts.mint.key();
+      denylist.bump = ctx.bumps.denylist;
+
       Ok(())
   }
 
-  pub fn transfer_hook(_ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
+  pub fn issue_credential(
+      ctx: Context<IssueCredential>,
+      verified: bool,
+      expires_at: i64,
+  ) -> Result<()> {
+      let credential = &mut ctx.accounts.human_credential;
+      credential.owner = ctx.accounts.owner.key();
+      credential.verified = verified;
+      credential.issued_at = Clock::get()?.unix_timestamp;
+      credential.expires_at = expires_at;
+      credential.bump = ctx.bumps.human_credential;
+
+      Ok(())
+  }
 
-      // TODO: human verification logic
+  pub fn update_extra_account_meta_list(
+      ctx: Context<UpdateExtraAccountMetaList>,
+  ) -> Result<()> {
+      let account_metas = extra_account_metas(&ctx.accounts.mint.key(), ctx.program_id)?;
+
+      let account_size = ExtraAccountMetaList::size_of(account_metas.len())? as u64;
+      let new_minimum_balance = Rent::get()?.minimum_balance(account_size as usize);
+
+      let extra_account_meta_list_info = ctx.accounts.extra_account_meta_list.to_account_info();
+      let current_lamports = extra_account_meta_list_info.lamports();
+
+      if new_minimum_balance > current_lamports {
+          transfer(
+              CpiContext::new(
+                  ctx.accounts.system_program.to_account_info(),
+                  Transfer {
+                      from: ctx.accounts.authority.to_account_info(),
+                      to: extra_account_meta_list_info.clone(),
+                  },
+              ),
+              new_minimum_balance - current_lamports,
+          )?;
+      } else if current_lamports > new_minimum_balance {
+          let refund = current_lamports - new_minimum_balance;
+          **extra_account_meta_list_info.try_borrow_mut_lamports()? -= refund;
+          **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += refund;
+      }
+
+      extra_account_meta_list_info.realloc(account_size as usize, false)?;
+
+      ExtraAccountMetaList::update::<ExecuteInstruction>(
+          &mut extra_account_meta_list_info.try_borrow_mut_data()?,
+          &account_metas,
+      )?;
+
+      // Idempotent: only touches `mint`/`bump`, never `blocked`, so a Denylist that already
+      // existed before this update keeps its entries.
+      let denylist = &mut ctx.accounts.denylist;
+      denylist.mint = ctx.accounts.mint.key();
+      denylist.bump = ctx.bumps.denylist;
 
       Ok(())
   }
 
-  // fallback instruction handler as workaround to anchor instruction discriminator check
-  pub fn fallback<'info>(
-      program_id: &Pubkey,
-      accounts: &'info [AccountInfo<'info>],
-      data: &[u8],
+  pub fn close_extra_account_meta_list(
+      ctx: Context<CloseExtraAccountMetaList>,
   ) -> Result<()> {
-      // Get the instructions sysvar account (last account)
-      let instructions_sysvar_info = accounts.last().unwrap();
+      let authority_info = ctx.accounts.authority.to_account_info();
+
+      let extra_account_meta_list_info = ctx.accounts.extra_account_meta_list.to_account_info();
+      let reclaimed_lamports = extra_account_meta_list_info.lamports();
+      **authority_info.try_borrow_mut_lamports()? += reclaimed_lamports;
+      **extra_account_meta_list_info.try_borrow_mut_lamports()? = 0;
+      extra_account_meta_list_info.try_borrow_mut_data()?.fill(0);
+
+      // Close the Denylist PDA created alongside the validation account too, so rent is fully
+      // reclaimed and a later `initialize_extra_account_meta_list` (which `init`s both) can
+      // recreate it.
+      let denylist_info = ctx.accounts.denylist.to_account_info();
+      let reclaimed_lamports = denylist_info.lamports();
+      **authority_info.try_borrow_mut_lamports()? += reclaimed_lamports;
+      **denylist_info.try_borrow_mut_lamports()? = 0;
+      denylist_info.try_borrow_mut_data()?.fill(0);
+
+      Ok(())
+  }
+
+  pub fn add_to_denylist(ctx: Context<AddToDenylist>, blocked_account: Pubkey) -> Result<()> {
+      let insert_at = match ctx.accounts.denylist.blocked.binary_search(&blocked_account) {
+          Ok(_) => return Ok(()),
+          Err(insert_at) => insert_at,
+      };
 
+      require!(
+          ctx.accounts.denylist.blocked.len() < MAX_DENYLIST_LEN,
+          ErrorCode::DenylistFull
+      );
+
+      let new_size = denylist_account_size(ctx.accounts.denylist.blocked.len() + 1);
+      let denylist_info = ctx.accounts.denylist.to_account_info();
+      let current_lamports = denylist_info.lamports();
+      let new_minimum_balance = Rent::get()?.minimum_balance(new_size);
+
+      if new_minimum_balance > current_lamports {
+          transfer(
+              CpiContext::new(
+                  ctx.accounts.system_program.to_account_info(),
+                  Transfer {
+                      from: ctx.accounts.admin.to_account_info(),
+                      to: denylist_info.clone(),
+                  },
+              ),
+              new_minimum_balance - current_lamports,
+          )?;
+      }
+      denylist_info.realloc(new_size, false)?;
+
+      ctx.accounts.denylist.blocked.insert(insert_at, blocked_account);
+
+      Ok(())
+  }
+
+  pub fn remove_from_denylist(ctx: Context<RemoveFromDenylist>, blocked_account: Pubkey) -> Result<()> {
+      let index = ctx
+          .accounts
+          .denylist
+          .blocked
+          .binary_search(&blocked_account)
+          .map_err(|_| error!(ErrorCode::NotInDenylist))?;
+      ctx.accounts.denylist.blocked.remove(index);
+
+      let new_size = denylist_account_size(ctx.accounts.denylist.blocked.len());
+      let denylist_info = ctx.accounts.denylist.to_account_info();
+      let current_lamports = denylist_info.lamports();
+      let new_minimum_balance = Rent::get()?.minimum_balance(new_size);
+
+      if current_lamports > new_minimum_balance {
+          let refund = current_lamports - new_minimum_balance;
+          **denylist_info.try_borrow_mut_lamports()? -= refund;
+          **ctx.accounts.admin.to_account_info().try_borrow_mut_lamports()? += refund;
+      }
+      denylist_info.realloc(new_size, false)?;
+
+      Ok(())
+  }
+
+  // Discriminator override makes Anchor's normal dispatcher match the token program's
+  // `Execute` CPI directly, so no manual fallback/unpack shim is needed.
+  #[interface(spl_transfer_hook_interface::instruction::ExecuteInstruction)]
+  pub fn execute(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
       // Verify we're being called via CPI and by the token program
+      let instructions_sysvar_info = &ctx.accounts.instructions_sysvar;
       let current_ix_index = load_current_index_checked(instructions_sysvar_info)?;
       if current_ix_index == 0 {
           return err!(ErrorCode::UnauthorizedCaller);
       }
 
-      // Check that the caller is the Token-2022 program
       let caller_ix = load_instruction_at_checked(current_ix_index as usize, instructions_sysvar_info)?;
       if caller_ix.program_id != TOKEN_2022_PROGRAM_ID {
           return err!(ErrorCode::UnauthorizedCaller);
       }
 
-      let instruction = TransferHookInstruction::unpack(data)?;
+      let human_credential_info = ctx.accounts.human_credential.to_account_info();
+      if human_credential_info.owner != ctx.program_id || human_credential_info.data_is_empty() {
+          return err!(ErrorCode::HumanNotVerified);
+      }
+      let credential_data = human_credential_info.try_borrow_data()?;
+      let credential = HumanCredential::try_deserialize(&mut &credential_data[..])?;
+
+      if !credential.verified {
+          return err!(ErrorCode::HumanNotVerified);
+      }
 
-      // match instruction discriminator to transfer hook interface execute instruction  
-      // token2022 program CPIs this instruction on token transfer
-      match instruction {
-          TransferHookInstruction::Execute { amount } => {
-              let amount_bytes = amount.to_le_bytes();
+      if Clock::get()?.unix_timestamp > credential.expires_at {
+          return err!(ErrorCode::CredentialExpired);
+      }
 
-              // invoke custom transfer hook instruction on our program
-              __private::__global::transfer_hook(program_id, accounts, &amount_bytes)
-          }
-          _ => return err!(ErrorCode::InvalidInstruction),
+      let denylist = &ctx.accounts.denylist;
+      if denylist.blocked.binary_search(&ctx.accounts.owner.key()).is_ok()
+          || denylist.blocked.binary_search(&ctx.accounts.destination_token.key()).is_ok()
+      {
+          return err!(ErrorCode::BlockedAddress);
       }
+
+      Ok(())
   }
 }
 
@@ -145,20 +345,82 @@ pub struct InitializeExtraAccountMetaList<'info> {
       bump
   )]
   pub extra_account_meta_list: AccountInfo<'info>,
+  #[account(
+      init,
+      payer = payer,
+      space = 8 + 32 + 1 + 4,
+      seeds = [b"denylist", mint.key().as_ref()],
+      bump,
+  )]
+  pub denylist: Account<'info, Denylist>,
   pub mint: InterfaceAccount<'info, Mint>,
   pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub system_program: Program<'info, System>,
 }
 
-// Order of accounts matters for this struct.
-// The first 4 accounts are the accounts required for token transfer (source, mint, destination, owner)
-// Remaining accounts are the extra accounts required from the ExtraAccountMetaList account
-// These accounts are provided via CPI to this program from the token2022 program
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+  #[account(
+      mut,
+      constraint = mint.mint_authority == COption::Some(authority.key()) @ ErrorCode::UnauthorizedCaller,
+  )]
+  pub authority: Signer<'info>,
+  pub mint: InterfaceAccount<'info, Mint>,
+  /// CHECK: ExtraAccountMetaList Account, must use these seeds
+  #[account(
+      mut,
+      seeds = [b"extra-account-metas", mint.key().as_ref()],
+      bump
+  )]
+  pub extra_account_meta_list: AccountInfo<'info>,
+  // `init_if_needed` because `extra_account_metas()` always configures the Denylist PDA as an
+  // extra account: a mint that was set up before the denylist feature shipped has no Denylist
+  // account yet, and without creating it here, every transfer after this update would fail to
+  // deserialize it in `TransferHook`.
+  #[account(
+      init_if_needed,
+      payer = authority,
+      space = 8 + 32 + 1 + 4,
+      seeds = [b"denylist", mint.key().as_ref()],
+      bump,
+  )]
+  pub denylist: Account<'info, Denylist>,
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExtraAccountMetaList<'info> {
+  #[account(
+      mut,
+      constraint = mint.mint_authority == COption::Some(authority.key()) @ ErrorCode::UnauthorizedCaller,
+  )]
+  pub authority: Signer<'info>,
+  pub mint: InterfaceAccount<'info, Mint>,
+  /// CHECK: ExtraAccountMetaList Account, must use these seeds
+  #[account(
+      mut,
+      seeds = [b"extra-account-metas", mint.key().as_ref()],
+      bump
+  )]
+  pub extra_account_meta_list: AccountInfo<'info>,
+  #[account(
+      mut,
+      seeds = [b"denylist", mint.key().as_ref()],
+      bump = denylist.bump,
+  )]
+  pub denylist: Account<'info, Denylist>,
+}
+
+// Order of accounts matters for this struct. These are provided via CPI to this program
+// from the token2022 program, in the order resolved from the `Execute` account list plus
+// the extra accounts configured in `initialize_extra_account_meta_list`:
+//   0 source_token, 1 mint, 2 destination_token, 3 owner, 4 extra_account_meta_list (validation),
+//   5 human_credential (seeds = [b"human", owner]), 6 instructions_sysvar, 7 denylist
 #[derive(Accounts)]
 pub struct TransferHook<'info> {
   #[account(
-      token::mint = mint, 
+      token::mint = mint,
       token::authority = owner,
   )]
   pub source_token: InterfaceAccount<'info, TokenAccount>,
@@ -171,11 +433,99 @@ pub struct TransferHook<'info> {
   pub owner: UncheckedAccount<'info>,
   /// CHECK: ExtraAccountMetaList Account,
   #[account(
-      seeds = [b"extra-account-metas", mint.key().as_ref()], 
+      seeds = [b"extra-account-metas", mint.key().as_ref()],
       bump
   )]
   pub extra_account_meta_list: UncheckedAccount<'info>,
+  /// CHECK: HumanCredential PDA. Typed as `UncheckedAccount` rather than `Account` because an
+  /// owner who was never issued a credential has no account here at all, and that absence
+  /// must surface as `ErrorCode::HumanNotVerified` rather than Anchor's own deserialization
+  /// error — see the manual check in `execute`.
+  #[account(
+      seeds = [b"human", owner.key().as_ref()],
+      bump,
+  )]
+  pub human_credential: UncheckedAccount<'info>,
   /// CHECK: Instructions sysvar account used to verify CPI caller
   #[account(address = solana_program::sysvar::instructions::ID)]
   pub instructions_sysvar: AccountInfo<'info>,
+  #[account(
+      seeds = [b"denylist", mint.key().as_ref()],
+      bump = denylist.bump,
+  )]
+  pub denylist: Account<'info, Denylist>,
+}
+
+#[derive(Accounts)]
+pub struct IssueCredential<'info> {
+  #[account(
+      mut,
+      address = ATTESTATION_AUTHORITY @ ErrorCode::UnauthorizedCaller,
+  )]
+  pub authority: Signer<'info>,
+  /// CHECK: the token account owner the credential is being issued for
+  pub owner: UncheckedAccount<'info>,
+  #[account(
+      init_if_needed,
+      payer = authority,
+      space = 8 + HumanCredential::INIT_SPACE,
+      seeds = [b"human", owner.key().as_ref()],
+      bump,
+  )]
+  pub human_credential: Account<'info, HumanCredential>,
+  pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct HumanCredential {
+  pub owner: Pubkey,
+  pub verified: bool,
+  pub issued_at: i64,
+  pub expires_at: i64,
+  pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct AddToDenylist<'info> {
+  #[account(
+      mut,
+      address = DENYLIST_ADMIN @ ErrorCode::UnauthorizedCaller,
+  )]
+  pub admin: Signer<'info>,
+  pub mint: InterfaceAccount<'info, Mint>,
+  #[account(
+      mut,
+      seeds = [b"denylist", mint.key().as_ref()],
+      bump = denylist.bump,
+  )]
+  pub denylist: Account<'info, Denylist>,
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromDenylist<'info> {
+  #[account(
+      mut,
+      address = DENYLIST_ADMIN @ ErrorCode::UnauthorizedCaller,
+  )]
+  pub admin: Signer<'info>,
+  pub mint: InterfaceAccount<'info, Mint>,
+  #[account(
+      mut,
+      seeds = [b"denylist", mint.key().as_ref()],
+      bump = denylist.bump,
+  )]
+  pub denylist: Account<'info, Denylist>,
+}
+
+// A bounded, sorted set of blocked pubkeys for a mint, checked against the source owner and
+// destination token account on every transfer. Sorted so membership is a binary search rather
+// than a linear scan; reallocated on insert/remove so rent tracks the live entry count instead
+// of a worst-case upper bound.
+#[account]
+pub struct Denylist {
+  pub mint: Pubkey,
+  pub bump: u8,
+  pub blocked: Vec<Pubkey>,
 }
@@ -0,0 +1,236 @@
+//! Off-chain helper for integrators building a `TransferChecked` instruction against this
+//! mint. The common mistake when hand-rolling this (see the SPL transfer-hook helper fix) is
+//! resolving seed-based extra-account metas against the bare transfer keys instead of the full
+//! `Execute` account list, which must include the validation (`ExtraAccountMetaList`) account
+//! before any seed referencing it can be resolved. This module mirrors exactly what the token
+//! program does on-chain via CPI, so clients never have to hand-build the extra accounts.
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta,
+    seeds::Seed,
+    state::{ExtraAccountMetaList, TlvStateBorrowed},
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use std::future::Future;
+
+/// Errors returned while resolving this program's extra transfer-hook accounts off-chain.
+#[derive(Debug)]
+pub enum ResolveError {
+    InvalidInstructionAccounts,
+    ValidationAccountNotFound,
+    InvalidValidationAccount,
+    SeedAccountNotFound,
+}
+
+impl std::fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolveError::InvalidInstructionAccounts => {
+                write!(f, "instruction does not carry the base source/mint/destination/owner accounts")
+            }
+            ResolveError::ValidationAccountNotFound => write!(f, "validation account not found"),
+            ResolveError::InvalidValidationAccount => {
+                write!(f, "validation account data is not a valid ExtraAccountMetaList")
+            }
+            ResolveError::SeedAccountNotFound => write!(f, "seed-resolved account not found"),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Derives the `ExtraAccountMetaList` (validation) PDA for `mint` under `program_id`.
+pub fn get_extra_account_metas_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], program_id).0
+}
+
+/// Resolves this program's extra transfer-hook accounts and appends the validation account
+/// followed by them to `instruction`, an otherwise-complete `TransferChecked` instruction whose
+/// first four accounts are, in order, source, mint, destination, and owner.
+///
+/// `fetch_account_data_fn` is used both to fetch the validation account's TLV data and to
+/// resolve any `Seed::AccountData` entries against already-resolved accounts, so it must be
+/// able to answer for arbitrary pubkeys, not just the validation PDA.
+pub async fn resolve_extra_account_metas<F, Fut>(
+    instruction: &mut Instruction,
+    fetch_account_data_fn: F,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<(), ResolveError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = Option<Vec<u8>>>,
+{
+    if instruction.accounts.len() < 4 {
+        return Err(ResolveError::InvalidInstructionAccounts);
+    }
+    let source = instruction.accounts[0].pubkey;
+    let destination = instruction.accounts[2].pubkey;
+    let owner = instruction.accounts[3].pubkey;
+    let validation_pubkey = get_extra_account_metas_address(mint, program_id);
+
+    let validation_data = fetch_account_data_fn(validation_pubkey)
+        .await
+        .ok_or(ResolveError::ValidationAccountNotFound)?;
+    let tlv_state = TlvStateBorrowed::unpack(&validation_data)
+        .map_err(|_| ResolveError::InvalidValidationAccount)?;
+    let extra_metas = ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&tlv_state)
+        .map_err(|_| ResolveError::InvalidValidationAccount)?;
+
+    // The resolved account list mirrors the on-chain `Execute` CPI: source, mint, destination,
+    // owner, then the validation account itself, since `Seed::AccountKey` indices are relative
+    // to this full list, not to the caller's original transfer instruction.
+    let mut resolved: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(source, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(destination, false),
+        AccountMeta::new_readonly(owner, false),
+        AccountMeta::new_readonly(validation_pubkey, false),
+    ];
+
+    for extra_meta in extra_metas.data().iter() {
+        let pubkey = resolve_one(extra_meta, &resolved, &fetch_account_data_fn).await?;
+        resolved.push(AccountMeta {
+            pubkey,
+            is_signer: false,
+            is_writable: extra_meta.is_writable,
+        });
+    }
+
+    instruction.accounts.extend_from_slice(&resolved[4..]);
+
+    Ok(())
+}
+
+async fn resolve_one<F, Fut>(
+    extra_meta: &ExtraAccountMeta,
+    already_resolved: &[AccountMeta],
+    fetch_account_data_fn: &F,
+) -> Result<Pubkey, ResolveError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = Option<Vec<u8>>>,
+{
+    if let Some(pubkey) = extra_meta.fixed_pubkey() {
+        return Ok(pubkey);
+    }
+
+    let mut seed_bytes = Vec::new();
+    for seed in extra_meta.seeds() {
+        match seed {
+            Seed::Literal { bytes } => seed_bytes.push(bytes.clone()),
+            Seed::AccountKey { index } => {
+                let account = already_resolved
+                    .get(*index as usize)
+                    .ok_or(ResolveError::SeedAccountNotFound)?;
+                seed_bytes.push(account.pubkey.to_bytes().to_vec());
+            }
+            Seed::AccountData {
+                account_index,
+                data_index,
+                length,
+            } => {
+                let account = already_resolved
+                    .get(*account_index as usize)
+                    .ok_or(ResolveError::SeedAccountNotFound)?;
+                let data = fetch_account_data_fn(account.pubkey)
+                    .await
+                    .ok_or(ResolveError::SeedAccountNotFound)?;
+                let start = *data_index as usize;
+                let end = start + *length as usize;
+                seed_bytes.push(data.get(start..end).ok_or(ResolveError::SeedAccountNotFound)?.to_vec());
+            }
+        }
+    }
+
+    let seed_refs: Vec<&[u8]> = seed_bytes.iter().map(Vec::as_slice).collect();
+    Ok(Pubkey::find_program_address(&seed_refs, &extra_meta.program_id()).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::system_program;
+
+    // Mirrors the extra accounts configured in `initialize_extra_account_meta_list`, derived
+    // from the same `crate::extra_account_metas` helper the on-chain instruction uses so this
+    // test can't silently drift out of sync with the shipped account layout: the owner's
+    // HumanCredential PDA (seeds = [b"human", owner]), the instructions sysvar, then the
+    // per-mint Denylist PDA (seeds = [b"denylist", mint]).
+    fn expected_accounts(
+        program_id: &Pubkey,
+        source: &Pubkey,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        owner: &Pubkey,
+    ) -> Vec<Pubkey> {
+        let validation_pubkey = get_extra_account_metas_address(mint, program_id);
+        let (human_credential, _) =
+            Pubkey::find_program_address(&[b"human", owner.as_ref()], program_id);
+        let (denylist, _) =
+            Pubkey::find_program_address(&[b"denylist", mint.as_ref()], program_id);
+
+        vec![
+            *source,
+            *mint,
+            *destination,
+            *owner,
+            validation_pubkey,
+            human_credential,
+            solana_program::sysvar::instructions::ID,
+            denylist,
+        ]
+    }
+
+    #[tokio::test]
+    async fn resolves_human_credential_instructions_sysvar_and_denylist() {
+        let program_id = Pubkey::new_unique();
+        let source = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+
+        let account_metas = crate::extra_account_metas(&mint, &program_id).unwrap();
+        let account_size = ExtraAccountMetaList::size_of(account_metas.len()).unwrap();
+        let mut validation_data = vec![0u8; account_size];
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut validation_data, &account_metas)
+            .unwrap();
+
+        let validation_pubkey = get_extra_account_metas_address(&mint, &program_id);
+        let fetch_account_data_fn = move |pubkey: Pubkey| {
+            let validation_data = validation_data.clone();
+            async move {
+                if pubkey == validation_pubkey {
+                    Some(validation_data)
+                } else {
+                    None
+                }
+            }
+        };
+
+        let mut instruction = Instruction {
+            program_id: system_program::ID,
+            accounts: vec![
+                AccountMeta::new(source, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new_readonly(owner, true),
+            ],
+            data: vec![],
+        };
+
+        resolve_extra_account_metas(&mut instruction, fetch_account_data_fn, &mint, &program_id)
+            .await
+            .unwrap();
+
+        let resolved: Vec<Pubkey> = instruction.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            resolved,
+            expected_accounts(&program_id, &source, &mint, &destination, &owner)
+        );
+    }
+}